@@ -0,0 +1,98 @@
+use crate::matrix::Matrix;
+
+/// Activation function usable by a [`crate::network::Network`] layer.
+///
+/// `derivative` is expressed in terms of the already-activated output
+/// (i.e. `derivative(function(x))`), matching how [`crate::network::Network`]
+/// calls it during backpropagation.
+///
+/// This is necessarily per-scalar, so it cannot express an activation like
+/// softmax that normalizes across the whole output vector — see
+/// [`softmax`] for that case.
+pub trait ActivationFunc {
+    fn function(&self, x: f64) -> f64;
+    fn derivative(&self, x: f64) -> f64;
+
+    /// Stable identifier persisted alongside a saved network so it reloads
+    /// with the right activation per layer. See [`from_name`].
+    fn name(&self) -> &'static str;
+}
+
+#[derive(Default)]
+pub struct Sigmoid;
+
+impl ActivationFunc for Sigmoid {
+    fn function(&self, x: f64) -> f64 {
+        1.0 / (1.0 + (-x).exp())
+    }
+
+    fn derivative(&self, x: f64) -> f64 {
+        x * (1.0 - x)
+    }
+
+    fn name(&self) -> &'static str {
+        "sigmoid"
+    }
+}
+
+#[derive(Default)]
+pub struct ReLU;
+
+impl ActivationFunc for ReLU {
+    fn function(&self, x: f64) -> f64 {
+        x.max(0.0)
+    }
+
+    fn derivative(&self, x: f64) -> f64 {
+        if x > 0.0 {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "relu"
+    }
+}
+
+/// Reconstructs a boxed activation from the identifier [`ActivationFunc::name`]
+/// produced, for reloading a saved [`crate::network::Network`].
+pub fn from_name(name: &str) -> Box<dyn ActivationFunc> {
+    match name {
+        "sigmoid" => Box::new(Sigmoid),
+        "relu" => Box::new(ReLU),
+        other => panic!("Unknown activation function: {other}"),
+    }
+}
+
+/// Softmax (`exp(x_i) / Σ exp(x_j)`) over every value in `values`, normalized
+/// across the whole matrix (not per-row), numerically stabilized by
+/// subtracting the max before exponentiating. Only meaningful for a
+/// single-sample vector, e.g. the column [`crate::network::Network`] feeds it.
+///
+/// Not an [`ActivationFunc`] impl: softmax mixes every output together, so
+/// it can't be expressed as a per-scalar function. Applied directly by
+/// [`crate::network::Network`] on its output layer when
+/// [`crate::network::Network::set_softmax_output`] is enabled — the
+/// activation [`crate::cost::CrossEntropy`] expects to pair with.
+pub fn softmax(values: &Matrix) -> Matrix {
+    let max = values
+        .data
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    let exps: Vec<f64> = values.data.iter().flatten().map(|x| (x - max).exp()).collect();
+    let sum: f64 = exps.iter().sum();
+
+    let mut normalized = exps.into_iter().map(|x| x / sum);
+    let data = values
+        .data
+        .iter()
+        .map(|row| row.iter().map(|_| normalized.next().unwrap()).collect())
+        .collect();
+
+    Matrix::new(values.rows, values.cols, data)
+}
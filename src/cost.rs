@@ -0,0 +1,69 @@
+use crate::matrix::Matrix;
+
+/// Cost (loss) function paired with a [`crate::network::Network`].
+///
+/// `delta` returns the error signal for the output layer, in the same
+/// `target - output` direction [`crate::network::Network::back_propogate`]
+/// previously hard-coded. By default the network still multiplies this by
+/// the output layer's activation derivative; a cost function whose
+/// derivative cancels the activation derivative (e.g. softmax paired with
+/// [`CrossEntropy`]) can override [`CostFunction::skip_activation_derivative`]
+/// to opt out of that multiply.
+pub trait CostFunction {
+    /// Mean loss for a single sample, used for reporting/early stopping.
+    fn loss(output: &Matrix, target: &Matrix) -> f64;
+
+    /// Error signal fed into the output layer's gradient.
+    fn delta(output: &Matrix, target: &Matrix) -> Matrix;
+
+    /// Whether `delta` already accounts for the activation derivative.
+    fn skip_activation_derivative() -> bool {
+        false
+    }
+}
+
+pub struct MeanSquaredError;
+
+impl CostFunction for MeanSquaredError {
+    fn loss(output: &Matrix, target: &Matrix) -> f64 {
+        let diff = output.sub(target);
+        let sum: f64 = diff.data.iter().flatten().map(|x| x * x).sum();
+
+        sum / (diff.rows * diff.cols) as f64
+    }
+
+    fn delta(output: &Matrix, target: &Matrix) -> Matrix {
+        target.sub(output)
+    }
+}
+
+/// Categorical cross-entropy loss. Requires a softmax output layer (enable
+/// with [`crate::network::Network::set_softmax_output`], which applies
+/// [`crate::activations::softmax`] directly - softmax can't be expressed as
+/// a per-scalar [`crate::activations::ActivationFunc`]) so that the outputs
+/// sum to `1.0` and `delta` simplifies to `target - output` because the
+/// activation derivative cancels algebraically - see
+/// [`CostFunction::skip_activation_derivative`].
+pub struct CrossEntropy;
+
+impl CostFunction for CrossEntropy {
+    fn loss(output: &Matrix, target: &Matrix) -> f64 {
+        const EPSILON: f64 = 1e-12;
+
+        -target
+            .data
+            .iter()
+            .flatten()
+            .zip(output.data.iter().flatten())
+            .map(|(t, o)| t * (o + EPSILON).ln())
+            .sum::<f64>()
+    }
+
+    fn delta(output: &Matrix, target: &Matrix) -> Matrix {
+        target.sub(output)
+    }
+
+    fn skip_activation_derivative() -> bool {
+        true
+    }
+}
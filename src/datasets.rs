@@ -0,0 +1,82 @@
+//! Loaders for the IDX file format used by MNIST and similar datasets, so
+//! samples can be fed directly into [`crate::network::Network::train`].
+
+use std::io::{self, ErrorKind};
+
+const IMAGE_MAGIC: u32 = 0x0000_0803;
+const LABEL_MAGIC: u32 = 0x0000_0801;
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, message.to_string())
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> io::Result<u32> {
+    let slice = bytes
+        .get(offset..offset + 4)
+        .ok_or_else(|| invalid_data("Truncated IDX header"))?;
+
+    Ok(u32::from_be_bytes(slice.try_into().unwrap()))
+}
+
+/// Reads an IDX image file (e.g. `train-images-idx3-ubyte`) into row-major
+/// pixel vectors normalized to `0.0..=1.0`.
+pub fn load_idx_images(path: &str) -> io::Result<Vec<Vec<f64>>> {
+    let bytes = std::fs::read(path)?;
+
+    if read_u32(&bytes, 0)? != IMAGE_MAGIC {
+        return Err(invalid_data("Invalid IDX image magic number"));
+    }
+
+    let count = read_u32(&bytes, 4)? as usize;
+    let rows = read_u32(&bytes, 8)? as usize;
+    let cols = read_u32(&bytes, 12)? as usize;
+    let image_size = rows * cols;
+
+    if image_size == 0 {
+        return Err(invalid_data("IDX image header has zero rows/cols"));
+    }
+
+    let body_len = count
+        .checked_mul(image_size)
+        .ok_or_else(|| invalid_data("IDX image count overflow"))?;
+    let end = body_len
+        .checked_add(16)
+        .ok_or_else(|| invalid_data("IDX image count overflow"))?;
+
+    let image_bytes = bytes.get(16..end).ok_or_else(|| invalid_data("Truncated IDX image data"))?;
+
+    let images = image_bytes
+        .chunks(image_size)
+        .map(|image| image.iter().map(|&pixel| pixel as f64 / 255.0).collect())
+        .collect();
+
+    Ok(images)
+}
+
+/// Reads an IDX label file (e.g. `train-labels-idx1-ubyte`) into length-10
+/// one-hot vectors.
+pub fn load_idx_labels(path: &str) -> io::Result<Vec<Vec<f64>>> {
+    let bytes = std::fs::read(path)?;
+
+    if read_u32(&bytes, 0)? != LABEL_MAGIC {
+        return Err(invalid_data("Invalid IDX label magic number"));
+    }
+
+    let count = read_u32(&bytes, 4)? as usize;
+    let end = count.checked_add(8).ok_or_else(|| invalid_data("IDX label count overflow"))?;
+
+    let label_bytes = bytes.get(8..end).ok_or_else(|| invalid_data("Truncated IDX label data"))?;
+
+    let mut labels = Vec::with_capacity(count);
+    for &label in label_bytes {
+        if label as usize >= 10 {
+            return Err(invalid_data("IDX label out of range 0..=9"));
+        }
+
+        let mut one_hot = vec![0.0; 10];
+        one_hot[label as usize] = 1.0;
+        labels.push(one_hot);
+    }
+
+    Ok(labels)
+}
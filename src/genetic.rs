@@ -0,0 +1,82 @@
+//! Neuroevolution: treat a [`Network`] as an evolvable genome and evolve a
+//! population by fitness, without backpropagation. Useful for reinforcement
+//! style tasks where no target vectors exist.
+
+use rand::Rng;
+
+use crate::activations::ActivationFunc;
+use crate::cost::CostFunction;
+use crate::network::Network;
+
+/// Produces a child genome by picking each gene uniformly from either
+/// parent.
+pub fn crossover(a: &[f64], b: &[f64], rng: &mut impl Rng) -> Vec<f64> {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| if rng.gen_bool(0.5) { *x } else { *y })
+        .collect()
+}
+
+/// For each gene, with probability `chance`, adds `coeff * rand_in(-1.0..=1.0)`.
+pub fn mutate(genome: &mut [f64], chance: f64, coeff: f64, rng: &mut impl Rng) {
+    for gene in genome.iter_mut() {
+        if rng.gen_bool(chance) {
+            *gene += coeff * rng.gen_range(-1.0..=1.0);
+        }
+    }
+}
+
+/// Picks a genome from `population` with probability proportional to its
+/// fitness (roulette-wheel selection). Falls back to a uniform pick when
+/// `total_fitness` is non-positive (e.g. generation zero, before anything
+/// has been scored), since roulette-wheel selection has no weights to work
+/// with in that case.
+fn select<'a>(population: &'a [(Vec<f64>, f64)], total_fitness: f64, rng: &mut impl Rng) -> &'a [f64] {
+    if total_fitness <= 0.0 {
+        return &population[rng.gen_range(0..population.len())].0;
+    }
+
+    let mut pick = rng.gen_range(0.0..total_fitness);
+
+    for (genome, fitness) in population {
+        if pick < *fitness {
+            return genome;
+        }
+        pick -= fitness;
+    }
+
+    &population.last().unwrap().0
+}
+
+/// Produces the next generation of networks from a fitness-scored
+/// population, via fitness-proportionate selection, uniform crossover and
+/// mutation. `make_activations` builds a fresh activation list (one per
+/// non-input layer) for each child, matching the population's topology.
+pub fn evolve<C: CostFunction>(
+    population: &[(Network<C>, f64)],
+    layers: &[usize],
+    make_activations: impl Fn() -> Vec<Box<dyn ActivationFunc>>,
+    learning_rate: f64,
+    mutation_chance: f64,
+    mutation_coeff: f64,
+    rng: &mut impl Rng,
+) -> Vec<Network<C>> {
+    let genomes: Vec<(Vec<f64>, f64)> = population
+        .iter()
+        .map(|(network, fitness)| (network.to_genome(), *fitness))
+        .collect();
+
+    let total_fitness: f64 = genomes.iter().map(|(_, fitness)| fitness).sum();
+
+    genomes
+        .iter()
+        .map(|_| {
+            let parent_a = select(&genomes, total_fitness, rng);
+            let parent_b = select(&genomes, total_fitness, rng);
+            let mut child = crossover(parent_a, parent_b, rng);
+            mutate(&mut child, mutation_chance, mutation_coeff, rng);
+
+            Network::from_genome(layers, &child, make_activations(), learning_rate)
+        })
+        .collect()
+}
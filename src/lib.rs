@@ -0,0 +1,6 @@
+pub mod activations;
+pub mod cost;
+pub mod datasets;
+pub mod genetic;
+pub mod matrix;
+pub mod network;
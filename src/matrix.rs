@@ -0,0 +1,140 @@
+use std::ops::Index;
+use std::time::Instant;
+
+use nanoserde::{DeJson, SerJson};
+use rand::Rng;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// A simple row-major dense matrix used for every signal flowing through a
+/// [`crate::network::Network`].
+#[derive(Debug, Clone, SerJson, DeJson)]
+pub struct Matrix {
+    pub rows: usize,
+    pub cols: usize,
+    pub data: Vec<Vec<f64>>,
+}
+
+impl Matrix {
+    pub fn new(rows: usize, cols: usize, data: Vec<Vec<f64>>) -> Self {
+        Self { rows, cols, data }
+    }
+
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Self::new(rows, cols, vec![vec![0.0; cols]; rows])
+    }
+
+    pub fn ones(rows: usize, cols: usize) -> Self {
+        Self::new(rows, cols, vec![vec![1.0; cols]; rows])
+    }
+
+    /// Builds a matrix with values drawn uniformly from `-1.0..=1.0`.
+    pub fn random(rows: usize, cols: usize) -> Self {
+        let mut rng = rand::thread_rng();
+        let data = (0..rows)
+            .map(|_| (0..cols).map(|_| rng.gen_range(-1.0..=1.0)).collect())
+            .collect();
+
+        Self::new(rows, cols, data)
+    }
+
+    /// Builds a single-row matrix out of a flat vector of values.
+    pub fn row(values: Vec<f64>) -> Self {
+        let cols = values.len();
+        Self::new(1, cols, vec![values])
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut data = vec![vec![0.0; self.rows]; self.cols];
+        for (i, row) in self.data.iter().enumerate() {
+            for (j, value) in row.iter().enumerate() {
+                data[j][i] = *value;
+            }
+        }
+
+        Self::new(self.cols, self.rows, data)
+    }
+
+    pub fn add(&self, other: &Matrix) -> Self {
+        self.zip_map(other, |a, b| a + b)
+    }
+
+    pub fn sub(&self, other: &Matrix) -> Self {
+        self.zip_map(other, |a, b| a - b)
+    }
+
+    /// Element-wise (Hadamard) product.
+    pub fn dot(&self, other: &Matrix) -> Self {
+        self.zip_map(other, |a, b| a * b)
+    }
+
+    fn zip_map(&self, other: &Matrix, f: impl Fn(f64, f64) -> f64) -> Self {
+        if self.rows != other.rows || self.cols != other.cols {
+            panic!("Matrix dimension mismatch");
+        }
+
+        let data = self
+            .data
+            .iter()
+            .zip(&other.data)
+            .map(|(a, b)| a.iter().zip(b).map(|(x, y)| f(*x, *y)).collect())
+            .collect();
+
+        Self::new(self.rows, self.cols, data)
+    }
+
+    pub fn map(&self, f: impl Fn(f64) -> f64) -> Self {
+        let data = self
+            .data
+            .iter()
+            .map(|row| row.iter().map(|x| f(*x)).collect())
+            .collect();
+
+        Self::new(self.rows, self.cols, data)
+    }
+
+    /// Standard matrix product, `self * other`.
+    ///
+    /// With the `rayon` feature enabled, output rows are computed in
+    /// parallel; otherwise they're computed serially.
+    pub fn mul(&self, other: &Matrix) -> Self {
+        if self.cols != other.rows {
+            panic!("Matrix dimension mismatch");
+        }
+
+        let compute_row = |row: &Vec<f64>| -> Vec<f64> {
+            (0..other.cols)
+                .map(|j| row.iter().enumerate().map(|(k, x)| x * other.data[k][j]).sum())
+                .collect()
+        };
+
+        #[cfg(feature = "rayon")]
+        let data = self.data.par_iter().map(compute_row).collect();
+
+        #[cfg(not(feature = "rayon"))]
+        let data = self.data.iter().map(compute_row).collect();
+
+        Self::new(self.rows, other.cols, data)
+    }
+
+    /// Multiplies two random `n x n` matrices and returns the throughput of
+    /// the multiply in GFLOP/s.
+    pub fn benchmark(n: usize) -> f64 {
+        let a = Matrix::random(n, n);
+        let b = Matrix::random(n, n);
+
+        let start = Instant::now();
+        let _ = a.mul(&b);
+        let elapsed = start.elapsed().as_secs_f64();
+
+        (2.0 * (n as f64).powi(3)) / elapsed / 1e9
+    }
+}
+
+impl Index<usize> for Matrix {
+    type Output = Vec<f64>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
+    }
+}
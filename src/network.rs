@@ -2,10 +2,15 @@ use std::fs;
 use std::io::{stdout, Write};
 use std::marker::PhantomData;
 
-use crate::activations::*;
+use crate::activations::{self, ActivationFunc};
+use crate::cost::CostFunction;
 use crate::matrix::Matrix;
 
 use nanoserde::{DeJson, SerJson};
+use rand::seq::SliceRandom;
+
+/// Callback invoked after every epoch with the network and `(epoch, epochs)`.
+type EpochCallback<C> = Box<dyn FnMut(&Network<C>, u16, u16)>;
 
 #[derive(SerJson, DeJson)]
 struct Data {
@@ -13,20 +18,24 @@ struct Data {
     learning_rate: f64,
     weights: Vec<Matrix>,
     biases: Vec<Matrix>,
+    activations: Vec<String>,
+    output_softmax: bool,
 }
 
-impl<F: ActivationFunc> From<&Network<F>> for Data {
-    fn from(value: &Network<F>) -> Self {
+impl<C: CostFunction + 'static> From<&Network<C>> for Data {
+    fn from(value: &Network<C>) -> Self {
         Self {
             layers: value.layers.clone(),
             learning_rate: value.learning_rate,
             weights: value.weights.clone(),
             biases: value.biases.clone(),
+            activations: value.activations.iter().map(|a| a.name().to_string()).collect(),
+            output_softmax: value.output_softmax,
         }
     }
 }
 
-pub struct Network<F: ActivationFunc> {
+pub struct Network<C: CostFunction + 'static> {
     /// Size of each layers
     layers: Vec<usize>,
 
@@ -45,11 +54,55 @@ pub struct Network<F: ActivationFunc> {
     /// Learning rate of this nerual network
     learning_rate: f64,
 
-    _activation: PhantomData<F>,
+    /// Activation function used by each non-input layer, indexed the same
+    /// way as `weights`/`biases`. Ignored for the output layer when
+    /// `output_softmax` is set.
+    activations: Vec<Box<dyn ActivationFunc>>,
+
+    /// When set, the output layer is normalized with [`activations::softmax`]
+    /// instead of its entry in `activations`. See
+    /// [`Network::set_softmax_output`].
+    output_softmax: bool,
+
+    /// Whether sample order is permuted at the start of every epoch
+    shuffle_data: bool,
+
+    /// Number of samples whose gradients are accumulated before a single
+    /// weight/bias update is applied
+    batch_size: usize,
+
+    /// Called after every epoch, defaults to a progress print
+    on_epoch_callback: Option<EpochCallback<C>>,
+
+    /// Called after every epoch with that epoch's mean loss
+    on_error_callback: Option<Box<dyn FnMut(f64)>>,
+
+    _cost: PhantomData<C>,
 }
 
-impl<F: ActivationFunc> Network<F> {
-    pub fn new(layers: Vec<usize>, learning_rate: f64) -> Self {
+impl<C: CostFunction + 'static> Network<C> {
+    /// Convenience constructor for a network using the same activation `F`
+    /// at every layer. Use [`Network::with_activations`] to mix activations,
+    /// e.g. ReLU hidden layers with a sigmoid output; a softmax output layer
+    /// is enabled separately with [`Network::set_softmax_output`].
+    pub fn new<F: ActivationFunc + Default + 'static>(layers: Vec<usize>, learning_rate: f64) -> Self {
+        let activations = (0..layers.len() - 1)
+            .map(|_| Box::new(F::default()) as Box<dyn ActivationFunc>)
+            .collect();
+
+        Self::with_activations(layers, activations, learning_rate)
+    }
+
+    /// Builds a network with one activation function per non-input layer.
+    pub fn with_activations(
+        layers: Vec<usize>,
+        activations: Vec<Box<dyn ActivationFunc>>,
+        learning_rate: f64,
+    ) -> Self {
+        if activations.len() != layers.len() - 1 {
+            panic!("Expected one activation per non-input layer");
+        }
+
         let mut weights = Vec::with_capacity(layers.len() - 1);
         let mut biases = Vec::with_capacity(layers.len() - 1);
 
@@ -63,8 +116,14 @@ impl<F: ActivationFunc> Network<F> {
             weights,
             biases,
             learning_rate,
+            activations,
+            output_softmax: false,
+            shuffle_data: false,
+            batch_size: 1,
+            on_epoch_callback: Some(Box::new(default_epoch_callback)),
+            on_error_callback: None,
             data: vec![],
-            _activation: PhantomData,
+            _cost: PhantomData,
         }
     }
 
@@ -80,11 +139,119 @@ impl<F: ActivationFunc> Network<F> {
             weights: data.weights,
             biases: data.biases,
             learning_rate: data.learning_rate,
+            activations: data.activations.iter().map(|name| activations::from_name(name)).collect(),
+            output_softmax: data.output_softmax,
+            shuffle_data: false,
+            batch_size: 1,
+            on_epoch_callback: Some(Box::new(default_epoch_callback)),
+            on_error_callback: None,
             data: Vec::new(),
-            _activation: PhantomData,
+            _cost: PhantomData,
         })
     }
 
+    /// Permutes sample order at the start of every epoch in [`Network::train`].
+    pub fn set_shuffle_data(&mut self, shuffle: bool) {
+        self.shuffle_data = shuffle;
+    }
+
+    /// Normalizes the output layer with [`activations::softmax`] instead of
+    /// its entry in the `activations` passed to [`Network::with_activations`]
+    /// (that entry becomes unused). Requires a `C` whose
+    /// [`CostFunction::skip_activation_derivative`] returns `true`, e.g.
+    /// [`crate::cost::CrossEntropy`].
+    pub fn set_softmax_output(&mut self, enabled: bool) {
+        self.output_softmax = enabled;
+    }
+
+    /// Number of samples whose gradients are accumulated before a single
+    /// weight/bias update is applied. Defaults to `1` (one update per sample).
+    pub fn set_batch_size(&mut self, batch_size: usize) {
+        self.batch_size = batch_size.max(1);
+    }
+
+    /// Replaces the per-epoch callback, called with the network, the current
+    /// epoch (1-indexed) and the total number of epochs. Defaults to a
+    /// progress print.
+    pub fn on_epoch(&mut self, callback: impl FnMut(&Network<C>, u16, u16) + 'static) {
+        self.on_epoch_callback = Some(Box::new(callback));
+    }
+
+    /// Sets a callback invoked after every epoch with that epoch's mean loss,
+    /// useful for early stopping or custom logging.
+    pub fn on_error(&mut self, callback: impl FnMut(f64) + 'static) {
+        self.on_error_callback = Some(Box::new(callback));
+    }
+
+    /// Flattens every weight and bias matrix into one contiguous vector, in
+    /// layer order (all weights, then all biases), for use with
+    /// [`crate::genetic`].
+    pub fn to_genome(&self) -> Vec<f64> {
+        let mut genome = Vec::new();
+
+        for weight in &self.weights {
+            genome.extend(weight.data.iter().flatten());
+        }
+
+        for bias in &self.biases {
+            genome.extend(bias.data.iter().flatten());
+        }
+
+        genome
+    }
+
+    /// Rebuilds a network from a flat genome produced by [`Network::to_genome`],
+    /// given the same `layers` shape it was flattened from and one activation
+    /// per non-input layer.
+    pub fn from_genome(
+        layers: &[usize],
+        genome: &[f64],
+        activations: Vec<Box<dyn ActivationFunc>>,
+        learning_rate: f64,
+    ) -> Self {
+        if activations.len() != layers.len() - 1 {
+            panic!("Expected one activation per non-input layer");
+        }
+
+        let mut weights = Vec::with_capacity(layers.len() - 1);
+        let mut biases = Vec::with_capacity(layers.len() - 1);
+        let mut cursor = 0;
+
+        for i in 0..layers.len() - 1 {
+            let (rows, cols) = (layers[i + 1], layers[i]);
+            let data = genome[cursor..cursor + rows * cols]
+                .chunks(cols)
+                .map(|row| row.to_vec())
+                .collect();
+
+            weights.push(Matrix::new(rows, cols, data));
+            cursor += rows * cols;
+        }
+
+        for i in 0..layers.len() - 1 {
+            let rows = layers[i + 1];
+            let data = genome[cursor..cursor + rows].iter().map(|x| vec![*x]).collect();
+
+            biases.push(Matrix::new(rows, 1, data));
+            cursor += rows;
+        }
+
+        Self {
+            layers: layers.to_vec(),
+            weights,
+            biases,
+            learning_rate,
+            activations,
+            output_softmax: false,
+            shuffle_data: false,
+            batch_size: 1,
+            on_epoch_callback: Some(Box::new(default_epoch_callback)),
+            on_error_callback: None,
+            data: Vec::new(),
+            _cost: PhantomData,
+        }
+    }
+
     pub fn save(&self, file_path: &str) -> std::io::Result<()> {
         let mut file = fs::File::create(file_path)?;
 
@@ -103,10 +270,14 @@ impl<F: ActivationFunc> Network<F> {
         self.data = vec![current.clone()];
 
         for i in 0..self.layers.len() - 1 {
-            current = self.weights[i]
-                .mul(&current)
-                .add(&self.biases[i])
-                .map(F::function);
+            let pre_activation = self.weights[i].mul(&current).add(&self.biases[i]);
+
+            current = if self.output_softmax && i == self.layers.len() - 2 {
+                activations::softmax(&pre_activation)
+            } else {
+                let activation = &self.activations[i];
+                pre_activation.map(|x| activation.function(x))
+            };
 
             self.data.push(current.clone());
         }
@@ -114,39 +285,122 @@ impl<F: ActivationFunc> Network<F> {
         current.transpose()[0].to_owned()
     }
 
-    pub fn back_propogate(&mut self, outputs: Vec<f64>, targets: Vec<f64>) {
+    /// Computes the per-layer weight/bias gradients and sample loss without
+    /// applying them, so callers can accumulate them across a mini-batch.
+    fn compute_gradients(&self, outputs: Vec<f64>, targets: Vec<f64>) -> (Vec<Matrix>, Vec<Matrix>, f64) {
         if targets.len() != *self.layers.last().unwrap() {
             panic!("Invalid number of targets");
         }
 
-        let parsed = Matrix::row(outputs);
-        let mut errors = Matrix::row(targets).sub(&parsed);
-        let mut gradients = parsed.map(F::derivative);
+        if self.output_softmax && !C::skip_activation_derivative() {
+            panic!("softmax output layer requires a CostFunction that skips the activation derivative (e.g. CrossEntropy)");
+        }
+
+        let parsed = Matrix::row(outputs).transpose();
+        let target = Matrix::row(targets).transpose();
+        let loss = C::loss(&parsed, &target);
+
+        let output_activation = self.activations.last().unwrap();
+        let mut errors = C::delta(&parsed, &target);
+        let mut gradients = if C::skip_activation_derivative() {
+            Matrix::ones(parsed.rows, parsed.cols)
+        } else {
+            parsed.map(|x| output_activation.derivative(x))
+        };
+
+        let mut weight_deltas: Vec<Matrix> =
+            self.weights.iter().map(|w| Matrix::zeros(w.rows, w.cols)).collect();
+        let mut bias_deltas: Vec<Matrix> =
+            self.biases.iter().map(|b| Matrix::zeros(b.rows, b.cols)).collect();
 
         for i in (0..self.layers.len() - 1).rev() {
             gradients = gradients.dot(&errors).map(|x| x * self.learning_rate);
 
-            self.weights[i] = self.weights[i].add(&gradients.mul(&self.data[i].transpose()));
-            self.biases[i] = self.biases[i].add(&gradients);
+            weight_deltas[i] = gradients.mul(&self.data[i].transpose());
+            bias_deltas[i] = gradients.clone();
 
             errors = self.weights[i].transpose().mul(&errors);
-            gradients = self.data[i].map(F::derivative);
+
+            if i > 0 {
+                let activation = &self.activations[i - 1];
+                gradients = self.data[i].map(|x| activation.derivative(x));
+            }
+        }
+
+        (weight_deltas, bias_deltas, loss)
+    }
+
+    /// Runs one step of backpropagation and returns the sample's loss, as
+    /// computed by `C`.
+    pub fn back_propogate(&mut self, outputs: Vec<f64>, targets: Vec<f64>) -> f64 {
+        let (weight_deltas, bias_deltas, loss) = self.compute_gradients(outputs, targets);
+
+        for i in 0..self.weights.len() {
+            self.weights[i] = self.weights[i].add(&weight_deltas[i]);
+            self.biases[i] = self.biases[i].add(&bias_deltas[i]);
         }
+
+        loss
     }
 
     pub fn train(&mut self, inputs: Vec<Vec<f64>>, targets: Vec<Vec<f64>>, epochs: u16) {
-        for i in 1..=epochs {
-            if epochs < 100 || i % (epochs / 100) == 0 {
-                print!("\r[Log] Epoch {i} of {epochs}");
-                stdout().flush().unwrap();
+        let batch_size = self.batch_size;
+        let mut order: Vec<usize> = (0..inputs.len()).collect();
+
+        for epoch in 1..=epochs {
+            if self.shuffle_data {
+                order.shuffle(&mut rand::thread_rng());
             }
 
-            for j in 0..inputs.len() {
-                let outputs = self.feed_forward(inputs[j].clone());
-                self.back_propogate(outputs, targets[j].clone());
+            let mut total_loss = 0.0;
+
+            for batch in order.chunks(batch_size) {
+                let mut weight_sums: Vec<Matrix> =
+                    self.weights.iter().map(|w| Matrix::zeros(w.rows, w.cols)).collect();
+                let mut bias_sums: Vec<Matrix> =
+                    self.biases.iter().map(|b| Matrix::zeros(b.rows, b.cols)).collect();
+
+                for &j in batch {
+                    let outputs = self.feed_forward(inputs[j].clone());
+                    let (weight_deltas, bias_deltas, loss) =
+                        self.compute_gradients(outputs, targets[j].clone());
+
+                    for k in 0..weight_sums.len() {
+                        weight_sums[k] = weight_sums[k].add(&weight_deltas[k]);
+                        bias_sums[k] = bias_sums[k].add(&bias_deltas[k]);
+                    }
+
+                    total_loss += loss;
+                }
+
+                let scale = 1.0 / batch.len() as f64;
+                for k in 0..self.weights.len() {
+                    self.weights[k] = self.weights[k].add(&weight_sums[k].map(|x| x * scale));
+                    self.biases[k] = self.biases[k].add(&bias_sums[k].map(|x| x * scale));
+                }
+            }
+
+            if let Some(mut callback) = self.on_epoch_callback.take() {
+                callback(&*self, epoch, epochs);
+                self.on_epoch_callback = Some(callback);
+            }
+
+            if let Some(callback) = &mut self.on_error_callback {
+                callback(total_loss / inputs.len() as f64);
             }
         }
+    }
+}
+
+/// Default [`Network::on_epoch`] callback: prints a throttled progress line,
+/// matching the behaviour `train` used to hard-code.
+fn default_epoch_callback<C: CostFunction + 'static>(_network: &Network<C>, epoch: u16, epochs: u16) {
+    if epochs < 100 || epoch.is_multiple_of(epochs / 100) {
+        print!("\r[Log] Epoch {epoch} of {epochs}");
+        stdout().flush().unwrap();
+    }
 
+    if epoch == epochs {
         println!("\r[Log] Done training!                  ");
     }
 }